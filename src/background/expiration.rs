@@ -0,0 +1,110 @@
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::Utc;
+use log::{error, info};
+
+use crate::info_storages::models::file_info::FileInfo;
+
+use super::BackgroundRunner;
+
+/// Returns `true` for uploads that should be reclaimed: incomplete
+/// resumable uploads whose `created_at` is older than `ttl`.
+///
+/// A finished upload (`offset >= length`) is never expired, regardless
+/// of age, so only abandoned partial uploads are collected.
+#[must_use]
+pub fn is_expired(info: &FileInfo, ttl: Duration) -> bool {
+    let complete = matches!(info.length, Some(length) if info.offset >= length);
+    if complete {
+        return false;
+    }
+    let age = Utc::now().signed_duration_since(info.created_at);
+    match age.to_std() {
+        Ok(age) => age > ttl,
+        // A `created_at` in the future yields a negative age; not expired.
+        Err(_) => false,
+    }
+}
+
+/// Drives a periodic expiration sweep on the background runner.
+///
+/// Every `interval`, `list_expired` is asked for the uploads that have
+/// outlived `ttl`, and each is handed to `terminate`. The tick itself is
+/// enqueued as a cancellable job so a sweep that is merely queued when
+/// shutdown starts is dropped rather than delaying drain.
+pub fn spawn_sweep<L, Lf, T, Tf>(
+    runner: BackgroundRunner,
+    interval: Duration,
+    ttl: Duration,
+    list_expired: L,
+    terminate: T,
+) where
+    L: Fn(Duration) -> Lf + Send + Sync + Clone + 'static,
+    Lf: Future<Output = crate::RustusResult<Vec<FileInfo>>> + Send,
+    T: Fn(FileInfo) -> Tf + Send + Sync + Clone + 'static,
+    Tf: Future<Output = crate::RustusResult<()>> + Send,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let list_expired = list_expired.clone();
+            let terminate = terminate.clone();
+            runner.spawn_cancellable(async move {
+                match list_expired(ttl).await {
+                    Ok(expired) => {
+                        for info in expired {
+                            let id = info.id.clone();
+                            if let Err(err) = terminate(info).await {
+                                error!("Failed to reclaim expired upload {}: {}", id, err);
+                            } else {
+                                info!("Reclaimed expired upload {}", id);
+                            }
+                        }
+                    }
+                    Err(err) => error!("Expiration sweep failed to list uploads: {}", err),
+                }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn aged(offset: usize, length: Option<usize>, age: ChronoDuration) -> FileInfo {
+        let mut info = FileInfo::new_test();
+        info.offset = offset;
+        info.length = length;
+        info.created_at = Utc::now() - age;
+        info
+    }
+
+    #[test]
+    fn complete_upload_is_never_expired() {
+        // Old, but finished: must be kept regardless of age.
+        let info = aged(10, Some(10), ChronoDuration::hours(48));
+        assert!(!is_expired(&info, Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn future_created_at_is_not_expired() {
+        let info = aged(0, Some(10), ChronoDuration::hours(-1));
+        assert!(!is_expired(&info, Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn incomplete_past_ttl_is_expired() {
+        let info = aged(3, Some(10), ChronoDuration::hours(2));
+        assert!(is_expired(&info, Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn incomplete_within_ttl_is_not_expired() {
+        let info = aged(3, Some(10), ChronoDuration::seconds(30));
+        assert!(!is_expired(&info, Duration::from_secs(3600)));
+    }
+}
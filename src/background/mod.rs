@@ -0,0 +1,204 @@
+pub mod expiration;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use log::{debug, error};
+use tokio::sync::{mpsc, watch, Mutex, Notify};
+use tokio::task::JoinHandle;
+
+/// A unit of deferred work run off the request path.
+type Job = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Envelope distinguishing jobs that must always run from jobs that may
+/// be dropped once shutdown has begun.
+enum Message {
+    /// Must run even during drain (e.g. an in-flight hook delivery).
+    Durable(Job),
+    /// Safe to discard once the stop signal is set (e.g. a periodic sweep
+    /// tick that will simply be redone on the next boot).
+    Cancellable(Job),
+}
+
+/// Small pool of worker tasks fed by an [`mpsc`] queue.
+///
+/// Request handlers enqueue post-upload hook notifications and
+/// termination-time storage deletions instead of blocking on them, and a
+/// periodic sweep is driven from the same pool. On [`shutdown`] the queue
+/// is closed, queued cancellable jobs are discarded, and outstanding
+/// durable jobs are awaited so in-flight deliveries aren't lost.
+///
+/// [`shutdown`]: BackgroundRunner::shutdown
+#[derive(Clone)]
+pub struct BackgroundRunner {
+    sender: mpsc::UnboundedSender<Message>,
+    stop_tx: watch::Sender<bool>,
+    /// Wakes a parked worker whenever a job is enqueued, so the shared
+    /// receiver only has to be locked for a non-blocking `try_recv`.
+    notify: Arc<Notify>,
+    workers: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl BackgroundRunner {
+    /// Starts `workers` worker tasks (at least one), each draining the
+    /// shared queue until the channel closes or, once shutdown has been
+    /// signalled, until the queue is empty.
+    #[must_use]
+    pub fn start(workers: usize) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel::<Message>();
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let notify = Arc::new(Notify::new());
+
+        let mut handles = Vec::with_capacity(workers);
+        for idx in 0..workers.max(1) {
+            let receiver = receiver.clone();
+            let stop_rx = stop_rx.clone();
+            let notify = notify.clone();
+            handles.push(tokio::spawn(worker_loop(idx, receiver, notify, stop_rx)));
+        }
+
+        BackgroundRunner {
+            sender,
+            stop_tx,
+            notify,
+            workers: Arc::new(Mutex::new(handles)),
+        }
+    }
+
+    /// Enqueues a job that must run even if shutdown starts before it is
+    /// picked up.
+    pub fn spawn<F>(&self, job: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if self.sender.send(Message::Durable(Box::pin(job))).is_err() {
+            error!("Background runner is gone, dropping durable job.");
+            return;
+        }
+        self.notify.notify_one();
+    }
+
+    /// Enqueues a job that is dropped if it is still queued once shutdown
+    /// has begun.
+    pub fn spawn_cancellable<F>(&self, job: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if self
+            .sender
+            .send(Message::Cancellable(Box::pin(job)))
+            .is_err()
+        {
+            debug!("Background runner is gone, dropping cancellable job.");
+            return;
+        }
+        self.notify.notify_one();
+    }
+
+    /// Signals shutdown and awaits the worker tasks so outstanding durable
+    /// jobs drain before the process exits.
+    ///
+    /// The stop signal — not channel closure — is what makes the workers
+    /// exit, so drain completes even while other [`Clone`]s of this runner
+    /// still hold the queue open in request handlers.
+    pub async fn shutdown(self) {
+        let _ = self.stop_tx.send(true);
+        // Wake every parked worker so it observes the stop signal and
+        // drains, rather than waiting on a notification that may never come
+        // once senders go idle.
+        self.notify.notify_waiters();
+        // This clone's sender is no longer needed; any remaining clones
+        // keep the queue alive but the workers stop on the signal above.
+        drop(self.sender);
+
+        let handles = {
+            let mut guard = self.workers.lock().await;
+            std::mem::take(&mut *guard)
+        };
+        for handle in handles {
+            if let Err(err) = handle.await {
+                error!("Background worker panicked during shutdown: {}", err);
+            }
+        }
+    }
+}
+
+async fn worker_loop(
+    idx: usize,
+    receiver: Arc<Mutex<mpsc::UnboundedReceiver<Message>>>,
+    notify: Arc<Notify>,
+    mut stop_rx: watch::Receiver<bool>,
+) {
+    loop {
+        // Hold the receiver lock only for a non-blocking `try_recv`, never
+        // across an `await`, so reception isn't serialized across the pool.
+        let message = {
+            let mut guard = receiver.lock().await;
+            guard.try_recv()
+        };
+        match message {
+            Ok(message) => {
+                // A peer may have work too; wake one so pickup stays
+                // parallel instead of funnelling through this worker.
+                notify.notify_one();
+                match message {
+                    Message::Durable(job) => job.await,
+                    Message::Cancellable(job) => {
+                        if *stop_rx.borrow() {
+                            debug!("Worker {} dropped a cancellable job during drain.", idx);
+                        } else {
+                            job.await;
+                        }
+                    }
+                }
+            }
+            // Every sender was dropped: the runner is gone for good.
+            Err(mpsc::error::TryRecvError::Disconnected) => break,
+            Err(mpsc::error::TryRecvError::Empty) => {
+                // The backlog is drained; if we're stopping, we're done.
+                if *stop_rx.borrow() {
+                    break;
+                }
+                // Otherwise park until a job is enqueued or shutdown begins.
+                tokio::select! {
+                    biased;
+                    _ = notify.notified() => {}
+                    _ = stop_rx.changed() => {}
+                }
+            }
+        }
+    }
+    debug!("Background worker {} stopped.", idx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn drains_durable_jobs_on_shutdown() {
+        let runner = BackgroundRunner::start(2);
+        let counter = Arc::new(AtomicUsize::new(0));
+        for _ in 0..8 {
+            let counter = counter.clone();
+            runner.spawn(async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        runner.shutdown().await;
+        assert_eq!(counter.load(Ordering::SeqCst), 8);
+    }
+
+    #[tokio::test]
+    async fn shutdown_completes_with_live_clone() {
+        let runner = BackgroundRunner::start(1);
+        // A clone keeps a sender alive, as a request handler would. The
+        // stop signal — not channel closure — must still end the workers,
+        // so this shutdown has to return rather than deadlock.
+        let _clone = runner.clone();
+        runner.shutdown().await;
+    }
+}
@@ -126,6 +126,68 @@ impl FileInfo {
         })?
     }
 
+    /// Metadata key through which a client declares the expected sha256
+    /// digest of a concatenated final upload at creation time.
+    pub const CONCAT_CHECKSUM_KEY: &'static str = "Upload-Concat-Checksum";
+
+    /// Expected hex sha256 digest of the assembled final file, if the
+    /// client supplied one via [`FileInfo::CONCAT_CHECKSUM_KEY`].
+    pub fn expected_concat_sha256(&self) -> Option<&str> {
+        self.metadata
+            .get(Self::CONCAT_CHECKSUM_KEY)
+            .map(String::as_str)
+    }
+
+    /// Computes the sha256 of a concatenated final upload by streaming
+    /// every part, in `parts` order, through a single hasher and stores
+    /// the hex digest in `finalized_sha256`.
+    ///
+    /// `read_part` is called once per part id and must yield that part's
+    /// bytes; it is the caller's bridge to the concrete storage. When the
+    /// client declared an expected digest via
+    /// [`FileInfo::CONCAT_CHECKSUM_KEY`] the assembled hash is compared
+    /// against it, and a mismatch fails finalization with
+    /// [`RustusError::ChecksumMismatch`] so corruption across the merge is
+    /// caught end to end.
+    pub async fn finalize_concat_sha256<F, Fut>(&mut self, mut read_part: F) -> RustusResult<()>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: std::future::Future<Output = RustusResult<Vec<u8>>>,
+    {
+        if !self.is_final {
+            return Err(RustusError::UnableToWrite(
+                "Cannot finalize the hash of a non-final upload".into(),
+            ));
+        }
+
+        let mut hasher = Sha256::new();
+        let parts = self.parts.clone().unwrap_or_default();
+        for part in parts {
+            let bytes = read_part(part).await?;
+            hasher.update(&bytes);
+        }
+
+        let hex_str = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+
+        if let Some(expected) = self.expected_concat_sha256() {
+            if !expected.eq_ignore_ascii_case(&hex_str) {
+                error!(
+                    "Concatenation checksum mismatch: expected {}, got {}",
+                    expected, hex_str
+                );
+                return Err(RustusError::ChecksumMismatch);
+            }
+        }
+
+        log::debug!("Finalized concatenation sha256: {}", hex_str);
+        self.finalized_sha256 = Some(hex_str);
+        Ok(())
+    }
+
     pub fn finalize_sha256(&mut self) -> RustusResult<()> {
         let hash = self.sha256.clone().finalize();
         let hex_str = hash
@@ -149,3 +211,72 @@ impl FileInfo {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sha256_hex(bytes: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Two-part final upload; `read_part` yields `hello`/`world`.
+    fn final_two_parts() -> (FileInfo, String) {
+        let mut info = FileInfo::new_test();
+        info.is_final = true;
+        info.parts = Some(vec!["p1".into(), "p2".into()]);
+        (info, sha256_hex(b"helloworld"))
+    }
+
+    async fn read_part(id: String) -> RustusResult<Vec<u8>> {
+        match id.as_str() {
+            "p1" => Ok(b"hello".to_vec()),
+            "p2" => Ok(b"world".to_vec()),
+            other => panic!("unexpected part {other}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn stores_assembled_hash_without_expectation() {
+        let (mut info, expected) = final_two_parts();
+        info.finalize_concat_sha256(read_part).await.unwrap();
+        assert_eq!(info.finalized_sha256, Some(expected));
+    }
+
+    #[tokio::test]
+    async fn matching_expected_digest_succeeds() {
+        let (mut info, expected) = final_two_parts();
+        info.metadata
+            .insert(FileInfo::CONCAT_CHECKSUM_KEY.into(), expected.clone());
+        info.finalize_concat_sha256(read_part).await.unwrap();
+        assert_eq!(info.finalized_sha256, Some(expected));
+    }
+
+    #[tokio::test]
+    async fn diverging_expected_digest_fails() {
+        let (mut info, _) = final_two_parts();
+        info.metadata
+            .insert(FileInfo::CONCAT_CHECKSUM_KEY.into(), "deadbeef".into());
+        assert!(matches!(
+            info.finalize_concat_sha256(read_part).await,
+            Err(RustusError::ChecksumMismatch)
+        ));
+        assert!(info.finalized_sha256.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_non_final_upload() {
+        let mut info = FileInfo::new_test();
+        info.parts = Some(vec!["p1".into()]);
+        assert!(matches!(
+            info.finalize_concat_sha256(read_part).await,
+            Err(RustusError::UnableToWrite(_))
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+use std::str::FromStr;
+
+use crate::errors::RustusError;
+
+/// Enum of all supported tus protocol extensions.
+///
+/// The active set is resolved from the CLI and drives both the
+/// services mounted in [`super::setup`] and the value advertised
+/// in the `Tus-Extension` header.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Extensions {
+    Creation,
+    CreationWithUpload,
+    CreationDeferLength,
+    Termination,
+    Concatenation,
+    Getting,
+    Checksum,
+}
+
+impl Extensions {
+    /// Name of the extension as used in the `Tus-Extension` header.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Extensions::Creation => "creation",
+            Extensions::CreationWithUpload => "creation-with-upload",
+            Extensions::CreationDeferLength => "creation-defer-length",
+            Extensions::Termination => "termination",
+            Extensions::Concatenation => "concatenation",
+            Extensions::Getting => "getting",
+            Extensions::Checksum => "checksum",
+        }
+    }
+}
+
+impl FromStr for Extensions {
+    type Err = RustusError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "creation" => Ok(Extensions::Creation),
+            "creation-with-upload" => Ok(Extensions::CreationWithUpload),
+            "creation-defer-length" => Ok(Extensions::CreationDeferLength),
+            "termination" => Ok(Extensions::Termination),
+            "concatenation" => Ok(Extensions::Concatenation),
+            "getting" => Ok(Extensions::Getting),
+            "checksum" => Ok(Extensions::Checksum),
+            _ => Err(RustusError::UnknownExtension(value.to_string())),
+        }
+    }
+}
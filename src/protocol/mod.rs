@@ -2,11 +2,13 @@ use actix_web::web;
 
 use crate::RustusConf;
 
+mod checksum;
 mod core;
 mod creation;
 pub mod extensions;
 mod getting;
 mod termination;
+pub mod upload_guard;
 
 /// Configure TUS web application.
 ///
@@ -24,6 +26,9 @@ pub fn setup(app_conf: RustusConf) -> impl Fn(&mut web::ServiceConfig) {
                 extensions::Extensions::Getting => {
                     getting::add_extension(web_app);
                 }
+                extensions::Extensions::Checksum => {
+                    checksum::add_extension(web_app);
+                }
                 _ => {}
             }
         }
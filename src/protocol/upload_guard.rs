@@ -0,0 +1,309 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use actix_web::web::Bytes;
+use futures::Stream;
+use sha2::{Digest, Sha256};
+use tokio::time::{Instant, Sleep};
+
+use crate::errors::RustusError;
+
+/// How often the guard wakes itself to re-check the throughput invariant
+/// while the peer is sending nothing, so a pure-idle connection is timed
+/// out without waiting for the next chunk that may never arrive.
+const RATE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Limits applied to an incoming `PATCH` body.
+///
+/// Populated from the CLI into `RustusConf` and cloned into every
+/// [`UploadGuard`] that wraps a request payload.
+#[derive(Clone, Debug)]
+pub struct UploadGuardConfig {
+    /// Hard cap on the number of bytes accepted for a single request.
+    ///
+    /// When the resource length is known it is derived from
+    /// `FileInfo.length - offset`, so a client can never push more than
+    /// its declared `Upload-Length`.
+    pub max_bytes: Option<usize>,
+    /// Minimum sustained throughput, enforced once `grace` has elapsed.
+    pub min_bytes_per_second: Option<u64>,
+    /// Abort the stream if no bytes arrive for this long.
+    pub idle_timeout: Option<Duration>,
+    /// Initial window during which the throughput check is suspended,
+    /// giving slow TLS handshakes and thin first chunks some slack.
+    pub grace: Duration,
+}
+
+impl Default for UploadGuardConfig {
+    fn default() -> Self {
+        UploadGuardConfig {
+            max_bytes: None,
+            min_bytes_per_second: None,
+            idle_timeout: None,
+            grace: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Stream adapter that enforces size, throughput and idle invariants on
+/// a `PATCH` body before the bytes reach storage.
+///
+/// It never buffers the whole body; each chunk is inspected as it flows
+/// through. When any invariant is violated the stream yields a
+/// [`RustusError`] and the caller must not persist the advanced offset,
+/// so a trickling or oversized connection cannot hold an upload slot
+/// open or exceed the negotiated size.
+pub struct UploadGuard<S> {
+    inner: S,
+    config: UploadGuardConfig,
+    bytes_read: usize,
+    start_time: Option<Instant>,
+    last_chunk: Option<Instant>,
+    /// Optional running hash fed with every chunk, so the checksum/final
+    /// hashing is free of a second pass over the body.
+    sha256: Option<Sha256>,
+    /// Timer used to re-poll the guard while `inner` is stalled, so the
+    /// idle/throughput limits are enforced even when no bytes arrive.
+    timer: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S> UploadGuard<S> {
+    pub fn new(inner: S, config: UploadGuardConfig) -> Self {
+        UploadGuard {
+            inner,
+            config,
+            bytes_read: 0,
+            start_time: None,
+            last_chunk: None,
+            sha256: None,
+            timer: None,
+        }
+    }
+
+    /// Feed every byte that passes the guard into `hasher` as well.
+    #[must_use]
+    pub fn hashing(mut self, hasher: Sha256) -> Self {
+        self.sha256 = Some(hasher);
+        self
+    }
+
+    /// Number of bytes that have successfully passed the guard.
+    #[must_use]
+    pub fn bytes_read(&self) -> usize {
+        self.bytes_read
+    }
+
+    /// The running hash, if hashing was enabled via [`UploadGuard::hashing`].
+    #[must_use]
+    pub fn into_sha256(self) -> Option<Sha256> {
+        self.sha256
+    }
+
+    /// Checks the rate/idle invariants against the clock. Split out so it
+    /// stays testable without a live stream.
+    fn check_rate(&self, now: Instant) -> Result<(), RustusError> {
+        let start = match self.start_time {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+
+        if let (Some(idle), Some(last)) = (self.config.idle_timeout, self.last_chunk) {
+            // `>=` so the check trips exactly at the deadline the wake-up
+            // timer was armed for, rather than one poll late.
+            if now.duration_since(last) >= idle {
+                return Err(RustusError::UploadTimeout);
+            }
+        }
+
+        if let Some(min_rate) = self.config.min_bytes_per_second {
+            let elapsed = now.duration_since(start);
+            if elapsed > self.config.grace {
+                let rate = self.bytes_read as f64 / elapsed.as_secs_f64();
+                if rate < min_rate as f64 {
+                    return Err(RustusError::UploadTooSlow);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Next instant at which an idle/throughput check must run, so the
+    /// guard can arm a timer to wake itself while `inner` is stalled.
+    /// `None` when neither limit is configured and stalls need no policing.
+    fn next_deadline(&self, now: Instant) -> Option<Instant> {
+        let mut deadline = None;
+        if let (Some(idle), Some(last)) = (self.config.idle_timeout, self.last_chunk) {
+            deadline = Some(last + idle);
+        }
+        if self.config.min_bytes_per_second.is_some() {
+            let tick = now + RATE_CHECK_INTERVAL;
+            deadline = Some(deadline.map_or(tick, |d: Instant| d.min(tick)));
+        }
+        deadline
+    }
+
+    /// Arms (or re-arms) the wake-up timer for the next deadline and polls
+    /// it. Returns `true` when the deadline has already elapsed, signalling
+    /// the caller to re-evaluate [`check_rate`] immediately.
+    ///
+    /// [`check_rate`]: UploadGuard::check_rate
+    fn poll_timer(&mut self, now: Instant, cx: &mut Context<'_>) -> bool {
+        let Some(deadline) = self.next_deadline(now) else {
+            self.timer = None;
+            return false;
+        };
+        match self.timer.as_mut() {
+            Some(timer) => timer.as_mut().reset(deadline),
+            None => self.timer = Some(Box::pin(tokio::time::sleep_until(deadline))),
+        }
+        self.timer
+            .as_mut()
+            .expect("timer was just set")
+            .as_mut()
+            .poll(cx)
+            .is_ready()
+    }
+}
+
+impl<S, E> Stream for UploadGuard<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    RustusError: From<E>,
+{
+    type Item = Result<Bytes, RustusError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let now = Instant::now();
+            if self.start_time.is_none() {
+                self.start_time = Some(now);
+                self.last_chunk = Some(now);
+            }
+
+            // Enforce idle/rate limits even while the peer stalls.
+            if let Err(err) = self.check_rate(now) {
+                return Poll::Ready(Some(Err(err)));
+            }
+
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) => {
+                    let total = self.bytes_read + chunk.len();
+                    if let Some(max) = self.config.max_bytes {
+                        if total > max {
+                            return Poll::Ready(Some(Err(RustusError::UploadTooLarge)));
+                        }
+                    }
+                    self.bytes_read = total;
+                    self.last_chunk = Some(now);
+                    self.timer = None;
+                    if let Some(hasher) = self.sha256.as_mut() {
+                        hasher.update(&chunk);
+                    }
+                    return Poll::Ready(Some(Ok(chunk)));
+                }
+                Poll::Ready(Some(Err(err))) => {
+                    return Poll::Ready(Some(Err(RustusError::from(err))))
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                // Nothing arrived: arm the wake-up timer so an idle or
+                // trickling peer is still policed. If its deadline has
+                // already passed, loop back and let `check_rate` trip.
+                Poll::Pending => {
+                    if self.poll_timer(now, cx) {
+                        continue;
+                    }
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_ok_within_grace() {
+        let mut guard = UploadGuard::new(
+            futures::stream::empty::<Result<Bytes, RustusError>>(),
+            UploadGuardConfig {
+                min_bytes_per_second: Some(1000),
+                grace: Duration::from_secs(5),
+                ..UploadGuardConfig::default()
+            },
+        );
+        let start = Instant::now();
+        // No start_time yet => no enforcement.
+        assert!(guard.check_rate(start).is_ok());
+        // Nothing read yet, but 2s is still inside the 5s grace window.
+        guard.start_time = Some(start);
+        assert!(guard.check_rate(start + Duration::from_secs(2)).is_ok());
+    }
+
+    #[test]
+    fn rate_too_slow_after_grace() {
+        let mut guard = UploadGuard::new(
+            futures::stream::empty::<Result<Bytes, RustusError>>(),
+            UploadGuardConfig {
+                min_bytes_per_second: Some(1000),
+                grace: Duration::from_secs(1),
+                ..UploadGuardConfig::default()
+            },
+        );
+        let start = Instant::now();
+        guard.start_time = Some(start);
+        guard.bytes_read = 10;
+        let now = start + Duration::from_secs(10);
+        assert!(matches!(
+            guard.check_rate(now),
+            Err(RustusError::UploadTooSlow)
+        ));
+    }
+
+    #[tokio::test]
+    async fn max_bytes_overflow_is_rejected() {
+        use futures::StreamExt;
+
+        let chunks = vec![
+            Ok::<_, RustusError>(Bytes::from_static(b"aaaa")),
+            Ok(Bytes::from_static(b"bbbb")),
+        ];
+        let mut guard = UploadGuard::new(
+            futures::stream::iter(chunks),
+            UploadGuardConfig {
+                max_bytes: Some(6),
+                ..UploadGuardConfig::default()
+            },
+        );
+        // First chunk fits under the cap.
+        assert!(matches!(guard.next().await, Some(Ok(_))));
+        // The second pushes the total past it.
+        assert!(matches!(
+            guard.next().await,
+            Some(Err(RustusError::UploadTooLarge))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_timeout_trips_without_bytes() {
+        use futures::StreamExt;
+
+        // A peer that sends nothing must still be timed out; the paused
+        // clock auto-advances to the armed wake-up timer.
+        let mut guard = UploadGuard::new(
+            futures::stream::pending::<Result<Bytes, RustusError>>(),
+            UploadGuardConfig {
+                idle_timeout: Some(Duration::from_secs(2)),
+                ..UploadGuardConfig::default()
+            },
+        );
+        assert!(matches!(
+            guard.next().await,
+            Some(Err(RustusError::UploadTimeout))
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::info_storages::models::file_info::FileInfo;
+use crate::{errors::RustusError, RustusResult};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Claims carried by a signed upload token.
+///
+/// A token authorizes a single upload: the resource it may create (or a
+/// `file_id` it will be bound to on creation), a ceiling on its
+/// `Upload-Length`, metadata the creation must satisfy, and an expiry.
+/// Minting is the only thing an application gateway needs in order to
+/// hand out time-limited upload grants without proxying the bytes.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UploadClaims {
+    /// File id this token is bound to. `None` means "any id", which is
+    /// pinned to the id chosen at creation time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
+    /// Maximum `Upload-Length` the token permits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// Metadata entries the creation request must carry verbatim.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+    /// Expiry as a unix timestamp (seconds).
+    pub exp: i64,
+}
+
+impl UploadClaims {
+    /// Signs these claims with `secret`, producing a compact
+    /// `payload.signature` token. Both halves are URL-safe base64 without
+    /// padding so the token is safe to drop into an upload URL.
+    pub fn mint(&self, secret: &[u8]) -> RustusResult<String> {
+        let payload = serde_json::to_vec(self).map_err(RustusError::from)?;
+        let encoded = URL_SAFE_NO_PAD.encode(&payload);
+        let signature = sign(secret, encoded.as_bytes());
+        Ok(format!("{encoded}.{}", URL_SAFE_NO_PAD.encode(signature)))
+    }
+
+    /// Verifies `token`'s signature against `secret` and checks it has not
+    /// expired, returning the decoded claims.
+    pub fn verify(token: &str, secret: &[u8]) -> RustusResult<Self> {
+        let (encoded, signature) = token.split_once('.').ok_or(RustusError::Unauthorized)?;
+
+        let provided = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| RustusError::Unauthorized)?;
+        // `Mac::verify_slice` is constant time; rely on it rather than
+        // comparing the signature bytes directly.
+        let mut mac = HmacSha256::new_from_slice(secret).map_err(|_| RustusError::Unauthorized)?;
+        mac.update(encoded.as_bytes());
+        mac.verify_slice(&provided)
+            .map_err(|_| RustusError::Unauthorized)?;
+
+        let payload = URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|_| RustusError::Unauthorized)?;
+        let claims: UploadClaims = serde_json::from_slice(&payload).map_err(RustusError::from)?;
+
+        if claims.exp < Utc::now().timestamp() {
+            return Err(RustusError::Unauthorized);
+        }
+        Ok(claims)
+    }
+
+    /// Binds the token's claims onto a freshly created `FileInfo`,
+    /// rejecting a creation that violates any of them.
+    ///
+    /// The declared length must not exceed `max_length`, every metadata
+    /// constraint must be present verbatim, and a token pinned to a
+    /// specific `file_id` may only create that resource.
+    pub fn bind_creation(&self, info: &FileInfo) -> RustusResult<()> {
+        if let Some(file_id) = &self.file_id {
+            if file_id != &info.id {
+                return Err(RustusError::Unauthorized);
+            }
+        }
+        if let (Some(max), Some(length)) = (self.max_length, info.length) {
+            if length > max {
+                return Err(RustusError::Unauthorized);
+            }
+        }
+        for (key, value) in &self.metadata {
+            if info.metadata.get(key) != Some(value) {
+                return Err(RustusError::Unauthorized);
+            }
+        }
+        Ok(())
+    }
+
+    /// Derives a resource token bound to `file_id`.
+    ///
+    /// When a grant is minted with `file_id: None` (bind-on-creation), the
+    /// server calls this once the id is chosen and hands the derived token
+    /// back to the client, so later `PATCH`/`HEAD`/`DELETE` requests carry
+    /// an id-scoped grant that [`matches_resource`] can pin exactly.
+    ///
+    /// [`matches_resource`]: UploadClaims::matches_resource
+    #[must_use]
+    pub fn bind_to_resource(&self, file_id: &str) -> UploadClaims {
+        UploadClaims {
+            file_id: Some(file_id.to_owned()),
+            ..self.clone()
+        }
+    }
+
+    /// Checks a token presented on a later `PATCH`/`HEAD`/`DELETE` still
+    /// matches the resource it is acting on, so an upload URL leaked to a
+    /// third party can't be turned against an unrelated file.
+    ///
+    /// Only a token bound to a concrete `file_id` authorizes a later
+    /// request. A bind-on-creation grant (`file_id: None`) carries no
+    /// record of which resource it created, so it is rejected here: the
+    /// server exchanges it for its derived token via [`bind_to_resource`]
+    /// at creation and hands that back to the client. An unbound token
+    /// replayed on a later request therefore can't act as a master key
+    /// over every file matching its size/metadata constraints.
+    ///
+    /// [`bind_to_resource`]: UploadClaims::bind_to_resource
+    pub fn matches_resource(&self, info: &FileInfo) -> RustusResult<()> {
+        match &self.file_id {
+            Some(file_id) if file_id == &info.id => Ok(()),
+            _ => Err(RustusError::Unauthorized),
+        }
+    }
+}
+
+fn sign(secret: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(exp: i64) -> UploadClaims {
+        UploadClaims {
+            file_id: Some("abc".into()),
+            max_length: Some(100),
+            metadata: HashMap::new(),
+            exp,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let secret = b"topsecret";
+        let token = claims(Utc::now().timestamp() + 60).mint(secret).unwrap();
+        let decoded = UploadClaims::verify(&token, secret).unwrap();
+        assert_eq!(decoded.file_id.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn rejects_tampered_signature() {
+        let secret = b"topsecret";
+        let token = claims(Utc::now().timestamp() + 60).mint(secret).unwrap();
+        assert!(UploadClaims::verify(&token, b"other-secret").is_err());
+    }
+
+    #[test]
+    fn unbound_grant_rejected_on_resource() {
+        let claims = UploadClaims {
+            file_id: None,
+            max_length: None,
+            metadata: HashMap::new(),
+            exp: Utc::now().timestamp() + 60,
+        };
+        // An unbound bind-on-creation grant carries no created id, so it
+        // must be rejected on a later request rather than authorizing any
+        // file — it has to be exchanged for its derived token first.
+        let info = FileInfo::new_test();
+        assert!(matches!(
+            claims.matches_resource(&info),
+            Err(RustusError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn derived_token_pins_the_resource() {
+        let claims = UploadClaims {
+            file_id: None,
+            max_length: None,
+            metadata: HashMap::new(),
+            exp: Utc::now().timestamp() + 60,
+        };
+        let info = FileInfo::new_test();
+        let bound = claims.bind_to_resource(&info.id);
+        assert!(bound.matches_resource(&info).is_ok());
+
+        let other = FileInfo::new_test();
+        assert!(matches!(
+            bound.matches_resource(&other),
+            Err(RustusError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn rejects_expired() {
+        let secret = b"topsecret";
+        let token = claims(Utc::now().timestamp() - 1).mint(secret).unwrap();
+        assert!(matches!(
+            UploadClaims::verify(&token, secret),
+            Err(RustusError::Unauthorized)
+        ));
+    }
+}